@@ -31,15 +31,74 @@ pub struct RunOptions {
 
     /// Name of the function to invoke
     #[structopt(long, value_name = "FUNCTION")]
-    invoke: Option<String>,
+    pub invoke: Option<String>,
 
     /// Load WebAssembly module from the given FD (must be >=3)
     #[cfg(unix)]
-    #[structopt(long, value_name = "FD", parse(try_from_str = parse_module_fd))]
+    #[structopt(long, value_name = "FD", parse(try_from_str = parse_fd))]
     pub module_on_fd: Option<RawFd>,
 
+    /// Inherit a pre-bound, listening TCP socket from the given FD (must be
+    /// >=3); may be given multiple times, systemd/listenfd-style
+    #[cfg(unix)]
+    #[structopt(
+        long = "listen-on-fd",
+        number_of_values = 1,
+        value_name = "FD",
+        parse(try_from_str = parse_fd),
+    )]
+    pub listen_on_fds: Vec<RawFd>,
+
+    /// Maximum amount of fuel the workload may consume before it is trapped
+    #[structopt(long, value_name = "UNITS")]
+    pub max_fuel: Option<u64>,
+
+    /// Wall-clock timeout for the workload, in seconds
+    #[structopt(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Maximum linear memory size the workload may grow to, in bytes
+    #[structopt(long, value_name = "BYTES")]
+    pub max_memory: Option<usize>,
+
+    /// Maximum number of table elements (e.g. function references) the workload may grow to
+    #[structopt(long, value_name = "COUNT")]
+    pub max_table_elements: Option<u32>,
+
+    /// Treat the input module as an already ahead-of-time compiled artifact
+    /// produced by `--precompile-to`, and load it with `Module::deserialize`
+    /// instead of compiling it
+    #[structopt(long)]
+    pub precompiled: bool,
+
+    /// Instead of running the module, compile it ahead-of-time and write the
+    /// resulting serialized artifact to this path
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub precompile_to: Option<PathBuf>,
+
+    /// Redirect the guest's stdin from the given file (default: inherit)
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub stdin: Option<PathBuf>,
+
+    /// Redirect the guest's stdout to the given file (default: inherit)
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub stdout: Option<PathBuf>,
+
+    /// Redirect the guest's stderr to the given file (default: inherit)
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub stderr: Option<PathBuf>,
+
+    /// Grant the guest read/write access to a host directory, as
+    /// `HOST_PATH:GUEST_PATH`; may be given multiple times
+    #[structopt(
+        long = "preopen",
+        number_of_values = 1,
+        value_name = "HOST_PATH:GUEST_PATH",
+        parse(try_from_str = parse_preopen),
+    )]
+    pub preopens: Vec<(PathBuf, String)>,
+
     // TODO: --inherit-env
-    // TODO: --stdin, --stdout, --stderr
     /// Path of the WebAssembly module to run
     #[structopt(
         index = 1,
@@ -62,10 +121,18 @@ fn parse_env_var(s: &str) -> Result<(String, String)> {
     Ok((parts[0].to_owned(), parts[1].to_owned()))
 }
 
-fn parse_module_fd(s: &str) -> Result<RawFd> {
+fn parse_fd(s: &str) -> Result<RawFd> {
     let fd = RawFd::from_str(s)?;
     if fd <= 2 {
         bail!("FD must be >= 3");
     }
     Ok(fd)
 }
+
+fn parse_preopen(s: &str) -> Result<(PathBuf, String)> {
+    let parts: Vec<&str> = s.splitn(2, ':').collect();
+    if parts.len() != 2 {
+        bail!("must be of the form `HOST_PATH:GUEST_PATH`");
+    }
+    Ok((parts[0].into(), parts[1].to_owned()))
+}
@@ -19,4 +19,7 @@ pub(crate) struct DeployConfig {
     pub stdin: HandleFrom,
     pub stdout: HandleFrom,
     pub stderr: HandleFrom,
+    /// Host directories to grant the guest access to, as `(host_path,
+    /// guest_path)` pairs, mirroring WASI's capability-based preopens.
+    pub preopens: Vec<(PathBuf, String)>,
 }
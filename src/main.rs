@@ -28,11 +28,13 @@
 #![deny(clippy::all)]
 
 mod cli;
+mod config;
 mod workload;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use cli::RunOptions;
-use log::{debug, info};
+use config::{DeployConfig, HandleFrom};
+use log::{debug, error, info};
 use structopt::StructOpt;
 
 use std::fs::File;
@@ -40,10 +42,28 @@ use std::io::Read;
 #[cfg(unix)]
 use std::os::unix::io::FromRawFd;
 
+// `--module-on-fd` and each `--listen-on-fd` are later wrapped in owning
+// Rust values (`File`/`TcpListener`) via `from_raw_fd`, which is unsound if
+// two of them name the same fd: both values would believe they uniquely own
+// it, and closing one (e.g. on drop) would pull it out from under the
+// other. `parse_fd` only checks that each fd is individually `>= 3`, so
+// this must be checked separately, across all of them together.
+#[cfg(unix)]
+fn check_fds_disjoint(opts: &RunOptions) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    for fd in opts.module_on_fd.iter().chain(opts.listen_on_fds.iter()) {
+        if !seen.insert(fd) {
+            bail!("fd {} given more than once across --module-on-fd/--listen-on-fd", fd);
+        }
+    }
+    Ok(())
+}
+
 // SAFETY: If opts.module_on_fd is Some(fd) we'll use File::from_raw_fd(fd),
 // which is unsafe if something else is using that fd already. So this function
 // is safe as long as it is called before anything else opens a file/socket/etc.
-// (parse_module_fd() enforces fd >= 3, so we can ignore stdin/out/err.)
+// (parse_fd() enforces fd >= 3, so we can ignore stdin/out/err; check_fds_disjoint()
+// enforces that module_on_fd and listen_on_fds don't overlap each other.)
 unsafe fn get_module_reader(opts: &RunOptions) -> Result<File> {
     #[cfg(unix)]
     if let Some(fd) = opts.module_on_fd {
@@ -55,6 +75,23 @@ unsafe fn get_module_reader(opts: &RunOptions) -> Result<File> {
     File::open(path).with_context(|| format!("failed opening {:?}", path))
 }
 
+// SAFETY: Same contract as get_module_reader(): each fd in
+// opts.listen_on_fds is wrapped with TcpListener::from_raw_fd(fd), which is
+// unsafe if something else is using that fd already. So this function is
+// safe as long as it is called before anything else opens a file/socket/etc.
+// (parse_fd() enforces fd >= 3, and check_fds_disjoint() enforces that
+// listen_on_fds doesn't collide with itself or with module_on_fd.)
+#[cfg(unix)]
+unsafe fn get_listeners(opts: &RunOptions) -> Vec<std::net::TcpListener> {
+    opts.listen_on_fds
+        .iter()
+        .map(|&fd| {
+            info!("inheriting listening socket from fd {:?}", fd);
+            std::net::TcpListener::from_raw_fd(fd)
+        })
+        .collect()
+}
+
 fn main() -> Result<()> {
     // Initialize the logger, taking filtering and style settings from the
     // default env vars (RUST_LOG and RUST_LOG_STYLE).
@@ -67,20 +104,91 @@ fn main() -> Result<()> {
     let opts = cli::RunOptions::from_args();
     info!("opts: {:#?}", opts);
 
+    #[cfg(unix)]
+    check_fds_disjoint(&opts)?;
+
     // SAFETY: This is safe because we haven't opened anything else yet.
     let mut reader = unsafe { get_module_reader(&opts) }?;
     let mut bytes = Vec::new();
     reader.read_to_end(&mut bytes).context("loading module")?;
 
+    // SAFETY: This is safe because we haven't opened anything else besides
+    // the module reader above, and check_fds_disjoint() confirmed above that
+    // module_on_fd and listen_on_fds name a disjoint set of fds.
+    #[cfg(unix)]
+    let listeners = unsafe { get_listeners(&opts) };
+    #[cfg(not(unix))]
+    let listeners: Vec<std::net::TcpListener> = Vec::new();
+
     // FUTURE: measure opts.envs, opts.args, opts.wasm_features, etc
     // FUTURE: fork() the workload off into a separate memory space?
 
-    // TODO: configure wasmtime, stdio, etc.
+    let limits = workload::Limits {
+        max_fuel: opts.max_fuel,
+        timeout: opts.timeout.map(std::time::Duration::from_secs),
+        max_memory: opts.max_memory,
+        max_table_elements: opts.max_table_elements,
+    };
+
+    if let Some(path) = &opts.precompile_to {
+        info!("precompiling module to {:?}", path);
+        let artifact = workload::precompile(&bytes, &limits).context("precompiling module")?;
+        std::fs::write(path, artifact)
+            .with_context(|| format!("writing precompiled module to {:?}", path))?;
+        return Ok(());
+    }
+
+    let deploy_config = DeployConfig {
+        stdin: opts.stdin.map_or(HandleFrom::Inherit, HandleFrom::File),
+        stdout: opts.stdout.map_or(HandleFrom::Inherit, HandleFrom::File),
+        stderr: opts.stderr.map_or(HandleFrom::Inherit, HandleFrom::File),
+        preopens: opts.preopens,
+    };
+
     info!("running workload");
-    let result = workload::run(bytes, opts.args, opts.envs).expect("Failed to run workload");
-    info!("got result: {:#?}", result);
-    // TODO: exit with the resulting code, if the result is a return code
+    let code = match workload::run(
+        bytes,
+        opts.precompiled,
+        opts.args,
+        opts.envs,
+        opts.invoke,
+        listeners,
+        limits,
+        deploy_config,
+    ) {
+        Ok(result) => {
+            info!("got result: {:#?}", result);
+            0
+        }
+        Err(e) => {
+            // A guest calling `proc_exit` surfaces as a Wasm trap carrying
+            // its exit status; that must be checked before downcasting to
+            // `workload::Error`, since `workload::call()` wraps every trap
+            // (including a plain `proc_exit`) in a `workload::Error`
+            // context too.
+            let exit_status = e
+                .downcast_ref::<wasmtime::Trap>()
+                .and_then(wasmtime::Trap::i32_exit_status);
+            match exit_status {
+                Some(status) => status,
+                None => match e.downcast::<workload::Error>() {
+                    Ok(e) => {
+                        error!("workload failed: {}", e);
+                        e.into()
+                    }
+                    Err(e) if e.downcast_ref::<wasmtime::Trap>().is_some() => {
+                        error!("workload trapped: {:#}", e);
+                        70 // EX_SOFTWARE
+                    }
+                    Err(e) => {
+                        error!("workload failed: {:#}", e);
+                        1
+                    }
+                },
+            }
+        }
+    };
     // FUTURE: produce attestation report here
 
-    Ok(())
+    std::process::exit(code);
 }
@@ -67,7 +67,11 @@ impl TarDirEntry {
                             .to_str()
                             .ok_or(ErrorKind::InvalidInput)?
                             .to_string();
-                        let content = TarFileContents::new(content, entry.raw_file_position());
+                        let content = TarFileContents::new(
+                            content,
+                            entry.raw_file_position(),
+                            entry.header().size()?,
+                        );
                         map.insert(name, TarDirEntry::File(Box::new(content)));
                     }
                     _ => unreachable!(),
@@ -125,40 +129,25 @@ impl Into<VirtualDirEntry> for TarDirEntry {
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct TarFileContents {
     content: Rc<[u8]>,
-    offset: u64,
+    // Absolute byte offset of the file's data within `content`, and its
+    // logical (unpadded) length. Captured once at `populate` time so reads
+    // are plain slice copies instead of re-parsing the archive.
+    data_offset: u64,
+    size: u64,
 }
 
 impl TarFileContents {
-    fn new(content: Rc<[u8]>, offset: u64) -> Self {
-        Self { content, offset }
+    fn new(content: Rc<[u8]>, data_offset: u64, size: u64) -> Self {
+        Self {
+            content,
+            data_offset,
+            size,
+        }
     }
 
     pub(crate) fn as_any(&self) -> &dyn Any {
         self as &dyn Any
     }
-
-    fn get_entry<'a, 'b>(
-        entries: &'a mut tar::Entries<'a, &'b [u8]>,
-        offset: u64,
-    ) -> Result<tar::Entry<'a, &'b [u8]>> {
-        let entry = entries
-            .take_while(|e| e.is_ok())
-            .map(|e| e.unwrap())
-            .find(|e| e.raw_file_position() == offset);
-        if let Some(entry) = entry {
-            Ok(entry)
-        } else {
-            Err(types::Errno::Noent)
-        }
-    }
-
-    fn try_size(&self) -> Result<types::Filesize> {
-        let mut archive = tar::Archive::new(&*self.content);
-        let mut entries = archive.entries()?;
-        let entry = Self::get_entry(&mut entries, self.offset)?;
-        let size = entry.header().size()?;
-        Ok(size)
-    }
 }
 
 impl FileContents for TarFileContents {
@@ -167,7 +156,7 @@ impl FileContents for TarFileContents {
     }
 
     fn size(&self) -> types::Filesize {
-        self.try_size().unwrap_or(0)
+        self.size
     }
 
     fn resize(&mut self, _new_size: types::Filesize) -> Result<()> {
@@ -189,19 +178,21 @@ impl FileContents for TarFileContents {
     }
 
     fn pread(&self, buf: &mut [u8], offset: types::Filesize) -> Result<usize> {
-        let mut archive = tar::Archive::new(&*self.content);
-        let mut entries = archive.entries()?;
-        let mut entry = Self::get_entry(&mut entries, self.offset)?;
-
         let offset: usize = offset.try_into().map_err(|_| types::Errno::Inval)?;
-
-        let size: usize = entry.header().size()?.try_into()?;
+        let size: usize = self.size.try_into().map_err(|_| types::Errno::Inval)?;
         let data_remaining = size.saturating_sub(offset);
-
         let read_count = std::cmp::min(buf.len(), data_remaining);
 
-        std::io::copy(&mut entry.by_ref().take(offset as _), &mut std::io::sink())?;
-        entry.read_exact(&mut buf[..read_count])?;
+        // A seek/read past EOF (legal POSIX usage) must not panic: bail out
+        // before computing `start`, since `offset` is guest-controlled and
+        // may put it well past `self.content`'s length even though the
+        // resulting slice would be empty.
+        if read_count == 0 {
+            return Ok(0);
+        }
+
+        let start = self.data_offset as usize + offset;
+        buf[..read_count].copy_from_slice(&self.content[start..start + read_count]);
         Ok(read_count)
     }
 
@@ -248,4 +239,13 @@ pub(crate) mod test {
             Some(TarDirEntry::File(_))
         ));
     }
+
+    #[test]
+    fn pread_past_eof_does_not_panic() {
+        let content: Rc<[u8]> = Rc::from(vec![1u8, 2, 3, 4, 5].into_boxed_slice());
+        let file = TarFileContents::new(content, 0, 5);
+        let mut buf = [0u8; 4];
+        let read = file.pread(&mut buf, 1_000_000).unwrap();
+        assert_eq!(read, 0);
+    }
 }
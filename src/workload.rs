@@ -2,8 +2,12 @@
 
 use crate::config::{DeployConfig, HandleFrom};
 use anyhow::{bail, Context, Result};
+use cap_std::ambient_authority;
 use log::debug;
-use wasmtime_wasi::sync::WasiCtxBuilder;
+use std::path::Path;
+use std::time::Duration;
+use wasmtime_wasi::sync::file::File as WasiFile;
+use wasmtime_wasi::sync::{Dir, WasiCtxBuilder};
 
 /// The error codes of workload execution.
 #[derive(Debug)]
@@ -22,23 +26,87 @@ pub enum Error {
     WASIError(wasmtime_wasi::Error),
     /// Arguments or environment too large
     StringTableError,
+    /// a configured fuel, timeout or memory/table limit was exceeded
+    ResourceLimitExceeded,
 }
 
 use std::fmt;
 
-/* FIXME: either implement this properly *or* just use anyhow .context */
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "placeholder impl")
+        match self {
+            Error::ConfigurationError => write!(f, "configuration error"),
+            Error::ExportNotFound => write!(f, "export not found"),
+            Error::InstantiationFailed => write!(f, "module instantiation failed"),
+            Error::CallFailed => write!(f, "call failed"),
+            Error::IoError(e) => write!(f, "I/O error: {}", e),
+            Error::WASIError(e) => write!(f, "WASI error: {}", e),
+            Error::StringTableError => write!(f, "arguments or environment too large"),
+            Error::ResourceLimitExceeded => write!(f, "resource limit exceeded"),
+        }
     }
 }
 
-/// Runs a WebAssembly workload.
-pub fn run<T: AsRef<str>, U: AsRef<str>>(
-    bytes: impl AsRef<[u8]>,
-    args: impl IntoIterator<Item = T>,
-    envs: impl IntoIterator<Item = (U, U)>,
-) -> Result<Box<[wasmtime::Val]>> {
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IoError(e)
+    }
+}
+
+impl From<wasmtime_wasi::Error> for Error {
+    fn from(e: wasmtime_wasi::Error) -> Self {
+        Error::WASIError(e)
+    }
+}
+
+/// Maps a workload error to a process exit status, following the
+/// `sysexits.h` convention.
+impl From<Error> for i32 {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::ConfigurationError => 78, // EX_CONFIG
+            Error::StringTableError => 78,   // EX_CONFIG
+            Error::ExportNotFound => 65,     // EX_DATAERR
+            Error::InstantiationFailed => 70, // EX_SOFTWARE
+            Error::CallFailed => 70,         // EX_SOFTWARE
+            Error::IoError(_) => 74,         // EX_IOERR
+            Error::WASIError(_) => 70,       // EX_SOFTWARE
+            Error::ResourceLimitExceeded => 69, // EX_UNAVAILABLE
+        }
+    }
+}
+
+/// Resource limits applied to a running guest.
+///
+/// Each field is optional; a `None` leaves the corresponding resource
+/// unbounded.
+#[derive(Default, Debug)]
+pub struct Limits {
+    /// Maximum amount of fuel the guest may consume before trapping.
+    pub max_fuel: Option<u64>,
+    /// Wall-clock deadline after which the guest is interrupted.
+    pub timeout: Option<Duration>,
+    /// Maximum linear memory size, in bytes.
+    pub max_memory: Option<usize>,
+    /// Maximum number of table elements.
+    pub max_table_elements: Option<u32>,
+}
+
+/// The data stored alongside a [`wasmtime::Store`]: the guest's WASI context
+/// plus the [`wasmtime::ResourceLimiter`] enforcing [`Limits`].
+struct HostCtx {
+    wasi: wasmtime_wasi::WasiCtx,
+    limits: wasmtime::StoreLimits,
+}
+
+/// Builds the [`wasmtime::Config`] used both to run a module and to
+/// precompile one with [`precompile`]. Fuel metering and epoch interruption
+/// are instrumented into the generated code, so a precompiled artifact must
+/// be produced (and later loaded) with the same `limits` shape or
+/// `Module::deserialize` will reject it as incompatible with the engine.
+fn configure(limits: &Limits) -> wasmtime::Config {
     let mut wasmconfig = wasmtime::Config::new();
     // FIXME: get features from CLI / config object
     // Support module-linking (https://github.com/webassembly/module-linking)
@@ -47,20 +115,119 @@ pub fn run<T: AsRef<str>, U: AsRef<str>>(
     wasmconfig.wasm_multi_memory(true);
     // Prefer dynamic memory allocation style over static memory
     wasmconfig.static_memory_maximum_size(0);
+    if limits.max_fuel.is_some() {
+        wasmconfig.consume_fuel(true);
+    }
+    if limits.timeout.is_some() {
+        wasmconfig.epoch_interruption(true);
+    }
+    wasmconfig
+}
+
+/// Compiles `bytes` ahead-of-time into a serialized artifact that [`run`]
+/// can later load directly with `Module::deserialize`, skipping compilation
+/// inside the Keep. `limits` must match the [`Limits`] the artifact will be
+/// run with, since fuel/epoch instrumentation is baked in at compile time.
+pub fn precompile(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    let wasmconfig = configure(limits);
+    let engine = wasmtime::Engine::new(&wasmconfig).context("configuring engine")?;
+    engine
+        .precompile_module(bytes.as_ref())
+        .context("precompiling module")
+}
 
+/// Runs a WebAssembly workload.
+///
+/// If the module exports `_start` it is run as a WASI "command": `args` are
+/// passed through as the guest's `argv` and the `_start` export is called.
+/// Otherwise the module is treated as a "reactor": `_initialize` (if
+/// exported) is run to execute constructors, then `invoke` names the export
+/// to call, with `args` parsed into that function's parameter types.
+///
+/// If `precompiled` is set, `bytes` is treated not as raw Wasm but as an
+/// artifact previously produced by [`precompile`], and is loaded with
+/// `Module::deserialize` instead of being compiled, so the Keep need not
+/// run a full Cranelift compilation of untrusted input.
+pub fn run<T: AsRef<str>, U: AsRef<str>>(
+    bytes: impl AsRef<[u8]>,
+    precompiled: bool,
+    args: impl IntoIterator<Item = T>,
+    envs: impl IntoIterator<Item = (U, U)>,
+    invoke: Option<impl AsRef<str>>,
+    listeners: impl IntoIterator<Item = std::net::TcpListener>,
+    limits: Limits,
+    deploy_config: DeployConfig,
+) -> Result<Box<[wasmtime::Val]>> {
+    let wasmconfig = configure(&limits);
     let engine = wasmtime::Engine::new(&wasmconfig).context("configuring engine")?;
 
     // Set up linker and link WASI into engine
     let mut linker = wasmtime::Linker::new(&engine);
-    wasmtime_wasi::add_to_linker(&mut linker, |s| s).context("adding WASI")?;
+    wasmtime_wasi::add_to_linker(&mut linker, |ctx: &mut HostCtx| &mut ctx.wasi)
+        .context("adding WASI")?;
 
-    // Add args and envs to the WasiCtx
+    let module = if precompiled {
+        // SAFETY: `Module::deserialize` trusts that `bytes` is a well-formed
+        // artifact produced by this same engine's `precompile_module`, and
+        // has not been corrupted or tampered with; loading an untrusted or
+        // mismatched artifact is undefined behavior. `deserialize` does
+        // check the artifact's recorded engine settings against `engine`
+        // and errors out on a mismatch, but cannot verify the bytes are
+        // genuinely wasmtime-generated.
+        unsafe { wasmtime::Module::deserialize(&engine, bytes.as_ref()) }
+            .context("deserializing precompiled module")?
+    } else {
+        wasmtime::Module::from_binary(&engine, bytes.as_ref()).context("parsing module")?
+    };
+
+    // A module whose declared initial memory/table already exceeds the
+    // configured limits can never be instantiated; catch that up front and
+    // report it as `Error::ResourceLimitExceeded`, same as a fuel/timeout
+    // trap, rather than letting it fall through as a generic instantiation
+    // failure below. This only sees exported memories/tables (there's no
+    // API to inspect unexported ones ahead of instantiation); an unexported
+    // one that's oversized still fails instantiation, just without this
+    // more specific classification.
+    for export in module.exports() {
+        match export.ty() {
+            wasmtime::ExternType::Memory(ty) => {
+                if let Some(max_memory) = limits.max_memory {
+                    if ty.minimum() * 64 * 1024 > max_memory as u64 {
+                        return Err(Error::ResourceLimitExceeded)
+                            .context("module's initial memory exceeds the configured limit");
+                    }
+                }
+            }
+            wasmtime::ExternType::Table(ty) => {
+                if let Some(max_table_elements) = limits.max_table_elements {
+                    if ty.minimum() > max_table_elements {
+                        return Err(Error::ResourceLimitExceeded)
+                            .context("module's initial table size exceeds the configured limit");
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // A module exporting `_start` follows the WASI "command" ABI. Anything
+    // else is treated as a "reactor" module invoked by name.
+    let is_command = module
+        .exports()
+        .any(|e| e.name() == "_start" && matches!(e.ty(), wasmtime::ExternType::Func(_)));
+
+    let args: Vec<String> = args.into_iter().map(|arg| arg.as_ref().to_string()).collect();
+
+    // Add args and envs to the WasiCtx. Reactor modules have no `argv`;
+    // `args` are Wasm parameters for the invoked export instead.
     let mut wasi = WasiCtxBuilder::new();
-    for arg in args {
-        wasi = wasi
-            .arg(arg.as_ref())
-            .context(Error::StringTableError)
-            .context("adding args")?;
+    if is_command {
+        for arg in &args {
+            wasi = wasi
+                .arg(arg)
+                .context(Error::StringTableError)
+                .context("adding args")?;
+        }
     }
     for kv in envs {
         wasi = wasi
@@ -69,15 +236,9 @@ pub fn run<T: AsRef<str>, U: AsRef<str>>(
             .context("adding envs")?;
     }
 
-    // TODO: get this config from the caller.. set up filehandles & sockets, etc etc
-    let deploy_config = DeployConfig {
-        stdin: HandleFrom::Inherit,
-        stdout: HandleFrom::Inherit,
-        stderr: HandleFrom::Inherit,
-    };
     match deploy_config.stdin {
         HandleFrom::File(path) => {
-            bail!("HandleFrom::File() not implemented")
+            wasi = wasi.stdin(open_readable(&path)?);
         }
         HandleFrom::Inherit => {
             wasi = wasi.stdin(Box::new(wasmtime_wasi::stdio::stdin()));
@@ -87,7 +248,7 @@ pub fn run<T: AsRef<str>, U: AsRef<str>>(
 
     match deploy_config.stdout {
         HandleFrom::File(path) => {
-            bail!("HandleFrom::File() not implemented")
+            wasi = wasi.stdout(open_writable(&path)?);
         }
         HandleFrom::Inherit => {
             wasi = wasi.stdout(Box::new(wasmtime_wasi::stdio::stdout()));
@@ -97,7 +258,7 @@ pub fn run<T: AsRef<str>, U: AsRef<str>>(
 
     match deploy_config.stderr {
         HandleFrom::File(path) => {
-            bail!("HandleFrom::File() not implemented")
+            wasi = wasi.stderr(open_writable(&path)?);
         }
         HandleFrom::Inherit => {
             wasi = wasi.stderr(Box::new(wasmtime_wasi::stdio::stderr()));
@@ -105,21 +266,167 @@ pub fn run<T: AsRef<str>, U: AsRef<str>>(
         HandleFrom::Null => {}
     };
 
-    let mut store = wasmtime::Store::new(&engine, wasi.build());
-    let module =
-        wasmtime::Module::from_binary(&engine, bytes.as_ref()).context("parsing module")?;
+    // Grant the guest access to explicitly configured host directories,
+    // matching WASI's capability-based filesystem model.
+    for (host_path, guest_path) in &deploy_config.preopens {
+        let dir = Dir::open_ambient_dir(host_path, ambient_authority())
+            .map_err(Error::from)
+            .with_context(|| format!("opening preopened directory {:?}", host_path))?;
+        wasi = wasi
+            .preopened_dir(Box::new(dir), guest_path)
+            .with_context(|| format!("adding preopened directory {:?}", guest_path))?;
+    }
+
+    // Hand any inherited, pre-bound listening sockets to the guest as
+    // preopened sockets (systemd/listenfd-style), numbered right after
+    // stdio and the preopened directories added just above, which
+    // `WasiCtxBuilder::preopened_dir` auto-assigns starting at fd 3.
+    let mut next_fd = 3 + deploy_config.preopens.len() as u32;
+    for listener in listeners {
+        let fd = next_fd;
+        next_fd += 1;
+        let listener = wasmtime_wasi::sync::net::TcpListener::from_std(listener);
+        wasi = wasi
+            .preopened_socket(fd, listener)
+            .with_context(|| format!("adding preopened socket {}", fd))?;
+    }
+
+    let mut store_limits = wasmtime::StoreLimitsBuilder::new();
+    if let Some(max_memory) = limits.max_memory {
+        store_limits = store_limits.memory_size(max_memory);
+    }
+    if let Some(max_table_elements) = limits.max_table_elements {
+        store_limits = store_limits.table_elements(max_table_elements);
+    }
+    let host = HostCtx {
+        wasi: wasi.build(),
+        limits: store_limits.build(),
+    };
+    let mut store = wasmtime::Store::new(&engine, host);
+    store.limiter(|ctx| &mut ctx.limits);
+
+    if let Some(fuel) = limits.max_fuel {
+        store.add_fuel(fuel).context("adding fuel")?;
+    }
+
+    // Keep the guest's wall-clock deadline ticking in the background; when
+    // it's reached, the next epoch check inside the guest traps.
+    if let Some(timeout) = limits.timeout {
+        store.set_epoch_deadline(1);
+        let engine = engine.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            engine.increment_epoch();
+        });
+    }
+
     linker
         .module(&mut store, "", &module)
         .context("instantiation failed")?;
 
-    // TODO: use the --invoke FUNCTION name, if any
-    let func = linker
-        .get_default(&mut store, "")
-        .context(Error::ExportNotFound)
+    if is_command {
+        let func = linker
+            .get_default(&mut store, "")
+            .context(Error::ExportNotFound)
+            .context("export not found")?;
+
+        return call(func, &mut store, &[]);
+    }
+
+    // Reactor ABI: run constructors (if any), then call the requested export.
+    if let Some(init) = linker.get(&mut store, "", "_initialize") {
+        let init = init
+            .into_func()
+            .context("_initialize export is not a function")?;
+        call(init, &mut store, &[])?;
+    }
+
+    let name = invoke
+        .as_ref()
+        .map(|n| n.as_ref())
+        .ok_or(Error::ExportNotFound)
         .context("export not found")?;
 
-    func.call(store, Default::default())
-        .context(Error::CallFailed)
+    let func = linker
+        .get(&mut store, "", name)
+        .ok_or(Error::ExportNotFound)
+        .context("export not found")?
+        .into_func()
+        .ok_or(Error::ExportNotFound)
+        .context("export is not a function")?;
+
+    let params = parse_vals(func.ty(&store).params(), &args)?;
+    call(func, &mut store, &params)
+}
+
+/// Calls `func`, remapping fuel exhaustion and epoch-deadline traps to
+/// [`Error::ResourceLimitExceeded`] instead of a generic [`Error::CallFailed`].
+fn call(
+    func: wasmtime::Func,
+    store: &mut wasmtime::Store<HostCtx>,
+    params: &[wasmtime::Val],
+) -> Result<Box<[wasmtime::Val]>> {
+    func.call(store, params).map_err(|e| {
+        let is_limit_trap = matches!(
+            e.downcast_ref::<wasmtime::Trap>().and_then(wasmtime::Trap::trap_code),
+            Some(wasmtime::TrapCode::OutOfFuel) | Some(wasmtime::TrapCode::Interrupt)
+        );
+        if is_limit_trap {
+            e.context(Error::ResourceLimitExceeded)
+        } else {
+            e.context(Error::CallFailed)
+        }
+    })
+}
+
+fn open_readable(path: &Path) -> Result<Box<WasiFile>> {
+    let file = std::fs::File::open(path)
+        .map_err(Error::from)
+        .with_context(|| format!("opening {:?} for stdio", path))?;
+    Ok(Box::new(WasiFile::from_std(file)))
+}
+
+fn open_writable(path: &Path) -> Result<Box<WasiFile>> {
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .map_err(Error::from)
+        .with_context(|| format!("opening {:?} for stdio", path))?;
+    Ok(Box::new(WasiFile::from_std(file)))
+}
+
+fn parse_val(ty: wasmtime::ValType, arg: &str) -> Result<wasmtime::Val> {
+    Ok(match ty {
+        wasmtime::ValType::I32 => wasmtime::Val::I32(arg.parse().context("parsing i32 argument")?),
+        wasmtime::ValType::I64 => wasmtime::Val::I64(arg.parse().context("parsing i64 argument")?),
+        wasmtime::ValType::F32 => wasmtime::Val::F32(
+            arg.parse::<f32>()
+                .context("parsing f32 argument")?
+                .to_bits(),
+        ),
+        wasmtime::ValType::F64 => wasmtime::Val::F64(
+            arg.parse::<f64>()
+                .context("parsing f64 argument")?
+                .to_bits(),
+        ),
+        other => bail!("unsupported parameter type {:?} for --invoke", other),
+    })
+}
+
+fn parse_vals(
+    tys: impl ExactSizeIterator<Item = wasmtime::ValType>,
+    args: &[String],
+) -> Result<Vec<wasmtime::Val>> {
+    if tys.len() != args.len() {
+        bail!(
+            "invoked function expects {} argument(s), got {}",
+            tys.len(),
+            args.len()
+        );
+    }
+    tys.zip(args).map(|(ty, arg)| parse_val(ty, arg)).collect()
 }
 
 #[cfg(test)]
@@ -131,12 +438,44 @@ pub(crate) mod test {
     fn workload_run_return_1() {
         let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/return_1.wasm")).to_vec();
 
-        let results: Vec<i32> =
-            workload::run(&bytes, empty::<String>(), empty::<(String, String)>())
-                .unwrap()
-                .iter()
-                .map(|v| v.unwrap_i32())
-                .collect();
+        let results: Vec<i32> = workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits::default(),
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap()
+        .iter()
+        .map(|v| v.unwrap_i32())
+        .collect();
+
+        assert_eq!(results, vec![1]);
+    }
+
+    #[test]
+    fn workload_run_precompiled() {
+        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/return_1.wasm")).to_vec();
+        let limits = workload::Limits::default();
+        let artifact = workload::precompile(&bytes, &limits).unwrap();
+
+        let results: Vec<i32> = workload::run(
+            &artifact,
+            true,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            limits,
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap()
+        .iter()
+        .map(|v| v.unwrap_i32())
+        .collect();
 
         assert_eq!(results, vec![1]);
     }
@@ -144,8 +483,17 @@ pub(crate) mod test {
     #[test]
     fn workload_run_no_export() {
         let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/no_export.wasm")).to_vec();
-        let err =
-            workload::run(&bytes, empty::<String>(), empty::<(String, String)>()).unwrap_err();
+        let err = workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits::default(),
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap_err();
         match err.downcast_ref::<workload::Error>() {
             Some(workload::Error::ExportNotFound) => {}
             _ => panic!("unexpected error"),
@@ -157,6 +505,110 @@ pub(crate) mod test {
         };
     }
 
+    #[test]
+    fn workload_run_fuel_exhausted() {
+        let bytes =
+            include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/loop_forever.wasm")).to_vec();
+
+        let err = workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits {
+                max_fuel: Some(1_000),
+                ..Default::default()
+            },
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap_err();
+
+        match err.downcast_ref::<workload::Error>() {
+            Some(workload::Error::ResourceLimitExceeded) => {}
+            _ => panic!("unexpected error"),
+        };
+    }
+
+    #[test]
+    fn workload_run_timeout_exceeded() {
+        let bytes =
+            include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/loop_forever.wasm")).to_vec();
+
+        let err = workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits {
+                timeout: Some(std::time::Duration::from_millis(100)),
+                ..Default::default()
+            },
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap_err();
+
+        match err.downcast_ref::<workload::Error>() {
+            Some(workload::Error::ResourceLimitExceeded) => {}
+            _ => panic!("unexpected error"),
+        };
+    }
+
+    #[test]
+    fn workload_run_memory_limit_exceeded() {
+        let bytes =
+            include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/memory_limit.wasm")).to_vec();
+
+        let err = workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits {
+                max_memory: Some(64 * 1024), // 1 page; the module needs 2
+                ..Default::default()
+            },
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap_err();
+
+        match err.downcast_ref::<workload::Error>() {
+            Some(workload::Error::ResourceLimitExceeded) => {}
+            _ => panic!("unexpected error"),
+        };
+    }
+
+    #[test]
+    fn workload_run_table_limit_exceeded() {
+        let bytes =
+            include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/table_limit.wasm")).to_vec();
+
+        let err = workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits {
+                max_table_elements: Some(5), // the module needs 10
+                ..Default::default()
+            },
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap_err();
+
+        match err.downcast_ref::<workload::Error>() {
+            Some(workload::Error::ResourceLimitExceeded) => {}
+            _ => panic!("unexpected error"),
+        };
+    }
+
     #[test]
     fn workload_run_wasi_snapshot1() {
         let bytes =
@@ -164,8 +616,13 @@ pub(crate) mod test {
 
         let results: Vec<i32> = workload::run(
             &bytes,
+            false,
             vec!["a".to_string(), "b".to_string(), "c".to_string()],
             vec![("k", "v")],
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits::default(),
+            crate::config::DeployConfig::default(),
         )
         .unwrap()
         .iter()
@@ -175,6 +632,100 @@ pub(crate) mod test {
         assert_eq!(results, vec![3]);
     }
 
+    #[test]
+    fn workload_run_reactor_invoke() {
+        // No `_start` export, so this runs as a reactor: `_initialize` seeds
+        // a global to 10, then `add` is invoked by name with a CLI arg
+        // parsed into its i32 parameter.
+        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/reactor.wasm")).to_vec();
+
+        let results: Vec<i32> = workload::run(
+            &bytes,
+            false,
+            vec!["5".to_string()],
+            empty::<(String, String)>(),
+            Some("add"),
+            empty::<std::net::TcpListener>(),
+            workload::Limits::default(),
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap()
+        .iter()
+        .map(|v| v.unwrap_i32())
+        .collect();
+
+        assert_eq!(results, vec![15]);
+    }
+
+    #[test]
+    fn workload_run_listener_fd_after_preopens() {
+        // A preopened directory claims guest fd 3 (auto-assigned by
+        // `WasiCtxBuilder::preopened_dir`); an inherited listener's fd must
+        // be numbered after it, not collide with it. The guest opens a
+        // known file through fd 3: if the listener had clobbered (or
+        // otherwise collided with) the directory's fd, this `path_open`
+        // would fail and the guest would trap.
+        let bytes =
+            include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/preopen_probe.wasm")).to_vec();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+        let dir = std::env::temp_dir().join("enarx_wasmldr_test_listener_fd_after_preopens");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("probe.txt"), b"probe").unwrap();
+
+        workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            vec![listener],
+            workload::Limits::default(),
+            crate::config::DeployConfig {
+                stdin: crate::config::HandleFrom::Inherit,
+                stdout: crate::config::HandleFrom::Inherit,
+                stderr: crate::config::HandleFrom::Inherit,
+                preopens: vec![(dir.clone(), "tmp".to_string())],
+            },
+        )
+        .unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn workload_run_file_backed_stdio() {
+        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/fixtures/cat.wasm")).to_vec();
+
+        let dir = std::env::temp_dir();
+        let input_path = dir.join("enarx_wasmldr_test_file_backed_stdio.in");
+        let output_path = dir.join("enarx_wasmldr_test_file_backed_stdio.out");
+        std::fs::write(&input_path, b"hello, file-backed stdio").unwrap();
+
+        workload::run(
+            &bytes,
+            false,
+            empty::<String>(),
+            empty::<(String, String)>(),
+            None::<String>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits::default(),
+            crate::config::DeployConfig {
+                stdin: crate::config::HandleFrom::File(input_path.clone()),
+                stdout: crate::config::HandleFrom::File(output_path.clone()),
+                stderr: crate::config::HandleFrom::Null,
+                preopens: Vec::new(),
+            },
+        )
+        .unwrap();
+
+        let output = std::fs::read(&output_path).unwrap();
+        assert_eq!(output, b"hello, file-backed stdio");
+
+        std::fs::remove_file(&input_path).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+    }
+
     #[cfg(bundle_tests)]
     #[test]
     fn workload_run_bundled() {
@@ -184,7 +735,17 @@ pub(crate) mod test {
         ))
         .to_vec();
 
-        workload::run(&bytes, empty::<&str>(), empty::<(&str, &str)>()).unwrap();
+        workload::run(
+            &bytes,
+            false,
+            empty::<&str>(),
+            empty::<(&str, &str)>(),
+            None::<&str>,
+            empty::<std::net::TcpListener>(),
+            workload::Limits::default(),
+            crate::config::DeployConfig::default(),
+        )
+        .unwrap();
 
         let output = std::fs::read("stdout.txt").unwrap();
         assert_eq!(output, "Hello, world!\n".to_string().into_bytes());